@@ -1,4 +1,8 @@
+use std::fmt;
+
 use uom::si::f64::{Mass, Ratio};
+use uom::si::mass::gram;
+use uom::si::ratio::percent;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Bread {
@@ -52,7 +56,291 @@ impl Bread {
         self.starter_water / self.starter_flour()
     }
 
+    /// Style of the starter, `None` when there is no starter flour to compute a hydratation from.
+    pub fn starter_style(&self) -> Option<StarterStyle> {
+        if self.has_starter_flour() {
+            Some(StarterStyle::from_hydratation(self.starter_hydratation()))
+        } else {
+            None
+        }
+    }
+
+    fn has_starter_flour(&self) -> bool {
+        self.starter_flour().get::<gram>() > 0.
+    }
+
     pub fn starter_ratio(&self) -> Ratio {
         self.starter / self.total_flour
     }
+
+    pub fn salt_ratio(&self) -> Ratio {
+        self.salt / self.total_flour
+    }
+
+    /// Lists the ratios outside of usual baking ranges, which usually indicate a mistake in the inputs.
+    pub fn sanity_warnings(&self) -> Vec<SanityWarning> {
+        let hydratation = self.hydratation();
+        let salt_ratio = self.salt_ratio();
+        let starter_ratio = self.starter_ratio();
+
+        let mut warnings = Vec::new();
+
+        if hydratation.get::<percent>() < SanityWarning::MIN_HYDRATATION_PERCENT {
+            warnings.push(SanityWarning::LowHydratation(hydratation));
+        } else if hydratation.get::<percent>() > SanityWarning::MAX_HYDRATATION_PERCENT {
+            warnings.push(SanityWarning::HighHydratation(hydratation));
+        }
+
+        if salt_ratio.get::<percent>() > SanityWarning::MAX_SALT_PERCENT {
+            warnings.push(SanityWarning::HighSalt(salt_ratio));
+        }
+
+        if starter_ratio.get::<percent>() > SanityWarning::MAX_STARTER_PERCENT {
+            warnings.push(SanityWarning::HighStarter(starter_ratio));
+        }
+
+        warnings
+    }
+
+    /// Computes what changes when going from `self` to `other`.
+    pub fn compare(&self, other: &Bread) -> BreadDiff {
+        BreadDiff {
+            total_weight: other.total_weight() - self.total_weight(),
+            total_flour: other.total_flour - self.total_flour,
+            added_flour: other.added_flour - self.added_flour,
+            total_water: other.total_water - self.total_water,
+            added_water: other.added_water - self.added_water,
+            starter: other.starter - self.starter,
+            salt: other.salt - self.salt,
+            hydratation: other.hydratation() - self.hydratation(),
+            starter_hydratation: (self.has_starter_flour() && other.has_starter_flour())
+                .then(|| other.starter_hydratation() - self.starter_hydratation()),
+            starter_ratio: other.starter_ratio() - self.starter_ratio(),
+            salt_ratio: other.salt_ratio() - self.salt_ratio(),
+        }
+    }
+}
+
+/// Differences between two breads, positive when the compared bread has more.
+///
+/// Ratios are differences in baker's percentage points (relative to total flour).
+#[derive(Clone, Debug, PartialEq)]
+pub struct BreadDiff {
+    pub total_weight: Mass,
+    pub total_flour: Mass,
+    pub added_flour: Mass,
+    pub total_water: Mass,
+    pub added_water: Mass,
+    pub starter: Mass,
+    pub salt: Mass,
+    pub hydratation: Ratio,
+    /// `None` when either bread has no starter flour to compute a hydratation from
+    pub starter_hydratation: Option<Ratio>,
+    pub starter_ratio: Ratio,
+    pub salt_ratio: Ratio,
+}
+
+/// A ratio outside of usual baking ranges, see [`Bread::sanity_warnings`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SanityWarning {
+    LowHydratation(Ratio),
+    HighHydratation(Ratio),
+    HighSalt(Ratio),
+    HighStarter(Ratio),
+}
+
+impl SanityWarning {
+    pub const MIN_HYDRATATION_PERCENT: f64 = 50.;
+    pub const MAX_HYDRATATION_PERCENT: f64 = 110.;
+    /// Of the total flour
+    pub const MAX_SALT_PERCENT: f64 = 3.;
+    /// Of the total flour
+    pub const MAX_STARTER_PERCENT: f64 = 40.;
+}
+
+impl fmt::Display for SanityWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LowHydratation(ratio) => write!(
+                f,
+                "hydratation of {:.0}% is below {}%, is it a typo?",
+                ratio.get::<percent>(),
+                Self::MIN_HYDRATATION_PERCENT
+            ),
+            Self::HighHydratation(ratio) => write!(
+                f,
+                "hydratation of {:.0}% is above {}%, is it a typo?",
+                ratio.get::<percent>(),
+                Self::MAX_HYDRATATION_PERCENT
+            ),
+            Self::HighSalt(ratio) => write!(
+                f,
+                "salt at {:.1}% of the flour is above {}%, is it a typo?",
+                ratio.get::<percent>(),
+                Self::MAX_SALT_PERCENT
+            ),
+            Self::HighStarter(ratio) => write!(
+                f,
+                "starter at {:.0}% of the flour is above {}%, is it a typo?",
+                ratio.get::<percent>(),
+                Self::MAX_STARTER_PERCENT
+            ),
+        }
+    }
+}
+
+/// Broad family of a starter, by hydratation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StarterStyle {
+    /// Dough-like starter, 60% hydratation or less
+    Stiff,
+    /// Equal parts flour and water, give or take
+    Standard,
+    /// Batter-like starter, 125% hydratation or more
+    Liquid,
+}
+
+impl StarterStyle {
+    /// Hydratation up to which a starter is considered stiff.
+    pub const STIFF_MAX_HYDRATATION_PERCENT: f64 = 60.;
+
+    /// Hydratation from which a starter is considered liquid.
+    pub const LIQUID_MIN_HYDRATATION_PERCENT: f64 = 125.;
+
+    pub fn from_hydratation(hydratation: Ratio) -> Self {
+        let hydratation = hydratation.get::<percent>();
+
+        if hydratation <= Self::STIFF_MAX_HYDRATATION_PERCENT {
+            Self::Stiff
+        } else if hydratation >= Self::LIQUID_MIN_HYDRATATION_PERCENT {
+            Self::Liquid
+        } else {
+            Self::Standard
+        }
+    }
+
+    /// Typical hydratation of this style, suitable as a preset.
+    pub fn hydratation(self) -> Ratio {
+        match self {
+            Self::Stiff => Ratio::new::<percent>(50.),
+            Self::Standard => Ratio::new::<percent>(100.),
+            Self::Liquid => Ratio::new::<percent>(166.),
+        }
+    }
+}
+
+impl fmt::Display for StarterStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stiff => write!(f, "stiff"),
+            Self::Standard => write!(f, "standard"),
+            Self::Liquid => write!(f, "liquid"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bread(flour: f64, water: f64) -> Bread {
+        Bread {
+            total_flour: Mass::new::<gram>(flour),
+            added_flour: Mass::new::<gram>(flour - 50.),
+            total_water: Mass::new::<gram>(water),
+            added_water: Mass::new::<gram>(water - 50.),
+            starter: Mass::new::<gram>(100.),
+            starter_water: Mass::new::<gram>(50.),
+            salt: Mass::new::<gram>(flour * 0.02),
+        }
+    }
+
+    #[test]
+    fn compare_hydratation_change() {
+        let diff = bread(500., 350.).compare(&bread(500., 375.));
+
+        assert!((diff.total_weight.get::<gram>() - 25.).abs() < 1e-9);
+        assert!(diff.total_flour.get::<gram>().abs() < 1e-9);
+        assert!((diff.added_water.get::<gram>() - 25.).abs() < 1e-9);
+        assert!((diff.hydratation.get::<percent>() - 5.).abs() < 1e-9);
+        assert!(diff.salt_ratio.get::<percent>().abs() < 1e-9);
+    }
+
+    #[test]
+    fn compare_with_itself() {
+        let bread = bread(400., 300.);
+        let diff = bread.compare(&bread);
+
+        assert_eq!(diff.total_weight, Mass::new::<gram>(0.));
+        assert_eq!(diff.starter_ratio, Ratio::new::<percent>(0.));
+        assert_eq!(diff.starter_hydratation, Some(Ratio::new::<percent>(0.)));
+    }
+
+    #[test]
+    fn compare_without_starter() {
+        let with_starter = bread(500., 350.);
+        let without_starter = Bread {
+            starter: Mass::new::<gram>(0.),
+            starter_water: Mass::new::<gram>(0.),
+            ..with_starter.clone()
+        };
+
+        assert_eq!(with_starter.compare(&without_starter).starter_hydratation, None);
+        assert_eq!(without_starter.compare(&with_starter).starter_hydratation, None);
+        assert_eq!(without_starter.compare(&without_starter).starter_hydratation, None);
+    }
+
+    #[test]
+    fn sanity_warnings() {
+        assert_eq!(bread(500., 350.).sanity_warnings(), []);
+
+        let warnings = bread(500., 1500.).sanity_warnings();
+        assert!(matches!(warnings[..], [SanityWarning::HighHydratation(_)]));
+        assert_eq!(
+            warnings[0].to_string(),
+            "hydratation of 300% is above 110%, is it a typo?"
+        );
+
+        let warnings = bread(200., 80.).sanity_warnings();
+        assert!(matches!(
+            warnings[..],
+            [SanityWarning::LowHydratation(_), SanityWarning::HighStarter(_)]
+        ));
+
+        let salty = Bread {
+            salt: Mass::new::<gram>(25.),
+            ..bread(500., 350.)
+        };
+        assert!(matches!(salty.sanity_warnings()[..], [SanityWarning::HighSalt(_)]));
+    }
+
+    #[test]
+    fn starter_style_from_hydratation() {
+        for (hydratation, style) in [
+            (50., StarterStyle::Stiff),
+            (60., StarterStyle::Stiff),
+            (80., StarterStyle::Standard),
+            (100., StarterStyle::Standard),
+            (125., StarterStyle::Liquid),
+            (166., StarterStyle::Liquid),
+        ] {
+            assert_eq!(
+                StarterStyle::from_hydratation(Ratio::new::<percent>(hydratation)),
+                style
+            );
+        }
+
+        for style in [StarterStyle::Stiff, StarterStyle::Standard, StarterStyle::Liquid] {
+            assert_eq!(StarterStyle::from_hydratation(style.hydratation()), style);
+        }
+
+        assert_eq!(bread(500., 350.).starter_style(), Some(StarterStyle::Standard));
+
+        let without_starter = Bread {
+            starter: Mass::new::<gram>(0.),
+            starter_water: Mass::new::<gram>(0.),
+            ..bread(500., 350.)
+        };
+        assert_eq!(without_starter.starter_style(), None);
+    }
 }
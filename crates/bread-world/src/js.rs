@@ -0,0 +1,148 @@
+//! Plain JSON entry point to the solver, callable from any JS page.
+
+use serde::{Deserialize, Serialize};
+use uom::si::f64::{Mass, Ratio};
+use uom::si::mass::gram;
+use uom::si::ratio::percent;
+use wasm_bindgen::prelude::*;
+
+use crate::TargetBread;
+
+/// Problem as sent by JS: masses are in grams, ratios in percent.
+#[derive(Debug, Deserialize)]
+struct Problem {
+    target: Target,
+    hydratation: f64,
+    starter_hydratation: f64,
+    starter_ratio: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Target {
+    TotalWeight(f64),
+    Flour(f64),
+    AddedFlour(f64),
+    Starter(f64),
+}
+
+impl From<Target> for TargetBread {
+    fn from(target: Target) -> Self {
+        match target {
+            Target::TotalWeight(grams) => TargetBread::TotalWeight(Mass::new::<gram>(grams)),
+            Target::Flour(grams) => TargetBread::Flour(Mass::new::<gram>(grams)),
+            Target::AddedFlour(grams) => TargetBread::AddedFlour(Mass::new::<gram>(grams)),
+            Target::Starter(grams) => TargetBread::Starter(Mass::new::<gram>(grams)),
+        }
+    }
+}
+
+/// Solution as returned to JS, all masses in grams.
+#[derive(Debug, Serialize)]
+struct Solution {
+    total_weight: f64,
+    total_flour: f64,
+    added_flour: f64,
+    total_water: f64,
+    added_water: f64,
+    starter: f64,
+    starter_flour: f64,
+    starter_water: f64,
+    salt: f64,
+    /// Ratios outside of usual baking ranges, see [`Bread::sanity_warnings`](bread_world_models::Bread::sanity_warnings)
+    warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Failure {
+    error: String,
+}
+
+/// Solves a bread problem given as JSON and returns the solution as JSON.
+///
+/// Input: `{ "target": { "total_weight": 800 }, "hydratation": 70, "starter_hydratation": 50, "starter_ratio": 20 }`
+/// where the target is one of `total_weight`, `flour`, `added_flour` or `starter` in grams.
+///
+/// On invalid input, or when no bread can be solved, `{ "error": "..." }` is returned instead.
+#[wasm_bindgen]
+pub fn solve_json(problem_json: &str) -> String {
+    let output = match serde_json::from_str::<Problem>(problem_json) {
+        Ok(problem) => match crate::solve(
+            problem.target.into(),
+            Ratio::new::<percent>(problem.hydratation),
+            Ratio::new::<percent>(problem.starter_hydratation),
+            Ratio::new::<percent>(problem.starter_ratio),
+        ) {
+            Ok(bread) => serde_json::to_value(Solution {
+                total_weight: bread.total_weight().get::<gram>(),
+                total_flour: bread.total_flour().get::<gram>(),
+                added_flour: bread.added_flour().get::<gram>(),
+                total_water: bread.total_water().get::<gram>(),
+                added_water: bread.added_water().get::<gram>(),
+                starter: bread.starter().get::<gram>(),
+                starter_flour: bread.starter_flour().get::<gram>(),
+                starter_water: bread.starter_water().get::<gram>(),
+                salt: bread.salt.get::<gram>(),
+                warnings: bread.sanity_warnings().iter().map(ToString::to_string).collect(),
+            }),
+            Err(e) => serde_json::to_value(Failure {
+                error: format!("no solution: {e}"),
+            }),
+        },
+        Err(e) => serde_json::to_value(Failure {
+            error: format!("invalid problem: {e}"),
+        }),
+    };
+
+    output.expect("plain structs always serialize").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_json_by_flour() {
+        let output = solve_json(
+            r#"{ "target": { "flour": 400 }, "hydratation": 75, "starter_hydratation": 50, "starter_ratio": 20 }"#,
+        );
+        let output: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert!((output["total_flour"].as_f64().unwrap() - 400.).abs() < 0.5);
+        assert!((output["total_water"].as_f64().unwrap() - 300.).abs() < 0.5);
+        assert!((output["salt"].as_f64().unwrap() - 8.).abs() < 0.5);
+        assert_eq!(output["warnings"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn solve_json_warns_about_typos() {
+        let output = solve_json(
+            r#"{ "target": { "flour": 400 }, "hydratation": 750, "starter_hydratation": 50, "starter_ratio": 20 }"#,
+        );
+        let output: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(output["warnings"].as_array().unwrap().len(), 1);
+        assert!(output["warnings"][0]
+            .as_str()
+            .unwrap()
+            .starts_with("hydratation of 750%"));
+    }
+
+    #[test]
+    fn solve_json_without_solution() {
+        let output = solve_json(
+            r#"{ "target": { "starter": 100 }, "hydratation": 75, "starter_hydratation": 50, "starter_ratio": 0 }"#,
+        );
+        let output: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert!(output["error"].as_str().unwrap().starts_with("no solution"));
+    }
+
+    #[test]
+    fn solve_json_invalid_input() {
+        let output = solve_json(r#"{ "target": { "banana": 400 } }"#);
+        let output: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert!(output["error"].as_str().unwrap().starts_with("invalid problem"));
+    }
+}
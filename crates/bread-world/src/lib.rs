@@ -1,20 +1,43 @@
 #[macro_use]
 extern crate log;
 
+mod js;
+mod sweep;
+
+use std::fmt;
+
 use bread_world_models::Bread;
 use uom::si::f64::{Mass, Ratio};
 use uom::si::mass::gram;
-use uom::si::ratio::ratio;
-
-macro_rules! debug_assert_f64_eq {
-    ($a:expr, $b:expr) => {{
-        let epsilon = $a * 0.001;
-        debug_assert!(
-            $a - epsilon < $b && $a + epsilon > $b,
-            "left: {}, right: {}",
-            $a.value,
-            $b.value
-        )
+use uom::si::ratio::{percent, ratio};
+
+pub use crate::js::solve_json;
+pub use crate::sweep::{sweep, Sweep, SweepParameter, SweepPoint, MAX_SWEEP_POINTS};
+
+/// Default relative tolerance used by [`solve`].
+pub const DEFAULT_TOLERANCE: f64 = 0.001;
+
+/// How far, in grams, a dough ball may be from the requested weight before [`DoughBalls::is_off`] flags it.
+///
+/// Each of the four weighed ingredients is rounded to the gram, so the dough can end up to two grams lighter or
+/// heavier than solved, spread over all the balls.
+pub const BALL_WEIGHT_TOLERANCE: f64 = 1.;
+
+/// Relative precision of the LP solver: values this close to zero, relative to the total weight, are noise.
+const SOLVER_EPSILON: f64 = 1e-9;
+
+/// Returns [`SolveError::Inaccurate`] if `$a` and `$b` are further apart than the relative `$tolerance`.
+macro_rules! ensure_f64_eq {
+    ($name:expr, $a:expr, $b:expr, $tolerance:expr) => {{
+        let (a, b) = ($a, $b);
+        let epsilon = a.abs().max(b.abs()) * $tolerance;
+        let error = (a - b).abs();
+        if error.value.is_nan() || error > epsilon {
+            return Err(SolveError::Inaccurate(format!(
+                "{} is {} instead of {}",
+                $name, a.value, b.value
+            )));
+        }
     }};
 }
 
@@ -24,11 +47,37 @@ pub enum TargetBread {
     TotalWeight(Mass),
     /// How much flour to use
     Flour(Mass),
+    /// How much flour to weigh out, not counting the flour from the starter
+    AddedFlour(Mass),
     /// How much starter to use
     Starter(Mass),
 }
 
 impl TargetBread {
+    /// Targets the raw dough weight giving `baked_weight` once baked, `bake_loss` being the share of the dough
+    /// weight driven off in the oven (e.g. 900 g baked at 12 % loss needs about 1023 g of dough).
+    ///
+    /// Fails if `bake_loss` is not in `[0, 1)`.
+    pub fn baked_weight(baked_weight: Mass, bake_loss: Ratio) -> Result<Self, SolveError> {
+        let loss = bake_loss.get::<ratio>();
+
+        if !(0. ..1.).contains(&loss) {
+            return Err(SolveError::InvalidInput(format!(
+                "bake loss must be between 0% and 100% excluded, got {}%",
+                bake_loss.get::<percent>()
+            )));
+        }
+
+        Ok(Self::TotalWeight(baked_weight / (1. - loss)))
+    }
+
+    /// The targeted mass, whatever it applies to.
+    fn mass(self) -> Mass {
+        match self {
+            Self::TotalWeight(mass) | Self::Flour(mass) | Self::AddedFlour(mass) | Self::Starter(mass) => mass,
+        }
+    }
+
     fn total_weight_bound(self) -> ellp::Bound {
         if let Self::TotalWeight(mass) = self {
             ellp::Bound::Fixed(mass.get::<gram>())
@@ -45,6 +94,14 @@ impl TargetBread {
         }
     }
 
+    fn added_flour_bound(self) -> ellp::Bound {
+        if let Self::AddedFlour(mass) = self {
+            ellp::Bound::Fixed(mass.get::<gram>())
+        } else {
+            ellp::Bound::Free
+        }
+    }
+
     fn starter_bound(self) -> ellp::Bound {
         if let Self::Starter(mass) = self {
             ellp::Bound::Fixed(mass.get::<gram>())
@@ -54,9 +111,81 @@ impl TargetBread {
     }
 }
 
-pub fn solve(target: TargetBread, hydratation: Ratio, starter_hydratation: Ratio, starter_ratio: Ratio) -> Bread {
+/// Why no bread could be solved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SolveError {
+    /// The target and ratios contradict each other
+    Infeasible,
+    /// The target and ratios don't determine a single bread
+    Unbounded,
+    /// The LP solver itself failed
+    Solver(String),
+    /// An input is out of range
+    InvalidInput(String),
+    /// The solution doesn't match the requested ratios within the tolerance
+    Inaccurate(String),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Infeasible => write!(f, "the target and ratios contradict each other"),
+            Self::Unbounded => write!(f, "the target and ratios don't determine a single bread"),
+            Self::Solver(e) => write!(f, "solver failure: {e}"),
+            Self::InvalidInput(e) => write!(f, "invalid input: {e}"),
+            Self::Inaccurate(e) => write!(f, "inaccurate solution: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+pub fn solve(
+    target: TargetBread,
+    hydratation: Ratio,
+    starter_hydratation: Ratio,
+    starter_ratio: Ratio,
+) -> Result<Bread, SolveError> {
+    solve_with_tolerance(
+        target,
+        hydratation,
+        starter_hydratation,
+        starter_ratio,
+        DEFAULT_TOLERANCE,
+    )
+}
+
+/// Same as [`solve`], with an explicit relative `tolerance`.
+///
+/// The tolerance is the relative error allowed when verifying the solution against the requested ratios, a
+/// solution further off failing with [`SolveError::Inaccurate`]. It is unrelated to the LP solver's own numerical
+/// precision (around `1e-9`), and should stay well above it. Only values within that precision of zero are clamped
+/// to zero, so small quantities such as a pinch of starter are kept whatever the tolerance.
+///
+/// Fails with [`SolveError::InvalidInput`] if the tolerance is negative or not finite, or if the target doesn't
+/// weigh a finite amount above zero.
+pub fn solve_with_tolerance(
+    target: TargetBread,
+    hydratation: Ratio,
+    starter_hydratation: Ratio,
+    starter_ratio: Ratio,
+    tolerance: f64,
+) -> Result<Bread, SolveError> {
     use ellp::*;
 
+    if !tolerance.is_finite() || tolerance < 0. {
+        return Err(SolveError::InvalidInput(format!(
+            "tolerance must be a finite number of at least 0, got {tolerance}"
+        )));
+    }
+
+    let target_grams = target.mass().get::<gram>();
+    if !target_grams.is_finite() || target_grams <= 0. {
+        return Err(SolveError::InvalidInput(format!(
+            "the target must weigh more than 0 g, got {target_grams} g"
+        )));
+    }
+
     let mut prob = Problem::new();
 
     let total_weight = prob
@@ -67,7 +196,9 @@ pub fn solve(target: TargetBread, hydratation: Ratio, starter_hydratation: Ratio
         .add_var(1., target.flour_bound(), Some("total_flour".to_string()))
         .unwrap();
 
-    let added_flour = prob.add_var(1., Bound::Free, Some("added_flour".to_owned())).unwrap();
+    let added_flour = prob
+        .add_var(1., target.added_flour_bound(), Some("added_flour".to_owned()))
+        .unwrap();
 
     let total_water = prob.add_var(1., Bound::Free, Some("total_water".to_owned())).unwrap();
 
@@ -149,38 +280,142 @@ pub fn solve(target: TargetBread, hydratation: Ratio, starter_hydratation: Ratio
     debug!("Problem: {prob}");
 
     let solver = DualSimplexSolver::default();
-    let result = solver.solve(prob).unwrap();
+    let sol = match solver.solve(prob) {
+        Ok(SolverResult::Optimal(sol)) => sol,
+        Ok(SolverResult::Infeasible) => return Err(SolveError::Infeasible),
+        Ok(SolverResult::Unbounded) => return Err(SolveError::Unbounded),
+        Err(e) => return Err(SolveError::Solver(e.to_string())),
+    };
+
+    let sol = sol.x();
+
+    debug!("Solution: {sol}");
+
+    let threshold = sol[usize::from(total_weight)].abs() * SOLVER_EPSILON;
+    let clamp = |value: f64| if value.abs() <= threshold { 0. } else { value };
+
+    let bread = Bread {
+        total_flour: Mass::new::<gram>(clamp(sol[usize::from(total_flour)])),
+        added_flour: Mass::new::<gram>(clamp(sol[usize::from(added_flour)])),
+        total_water: Mass::new::<gram>(clamp(sol[usize::from(total_water)])),
+        added_water: Mass::new::<gram>(clamp(sol[usize::from(added_water)])),
+        starter: Mass::new::<gram>(clamp(sol[usize::from(starter)])),
+        starter_water: Mass::new::<gram>(clamp(sol[usize::from(starter_water)])),
+        salt: Mass::new::<gram>(clamp(sol[usize::from(salt)])),
+    };
+
+    ensure_f64_eq!(
+        "total weight",
+        bread.total_weight(),
+        Mass::new::<gram>(sol[usize::from(total_weight)]),
+        tolerance
+    );
+    ensure_f64_eq!("hydratation", bread.hydratation(), hydratation, tolerance);
+    ensure_f64_eq!(
+        "starter flour",
+        bread.starter_flour(),
+        Mass::new::<gram>(sol[usize::from(starter_flour)]),
+        tolerance
+    );
+    if bread.starter_flour().get::<gram>() != 0. {
+        ensure_f64_eq!(
+            "starter hydratation",
+            bread.starter_hydratation(),
+            starter_hydratation,
+            tolerance
+        );
+    }
+    ensure_f64_eq!("starter ratio", bread.starter_ratio(), starter_ratio, tolerance);
 
-    if let SolverResult::Optimal(sol) = result {
-        let sol = sol.x();
+    Ok(bread)
+}
 
-        debug!("Solution: {sol}");
+/// A bread portioned into dough balls, see [`solve_dough_balls`].
+#[derive(Clone, Debug)]
+pub struct DoughBalls {
+    pub bread: Bread,
+    pub count: u32,
+    /// Requested weight of each ball
+    pub ball_weight: Mass,
+}
 
-        let bread = Bread {
-            total_flour: Mass::new::<gram>(sol[usize::from(total_flour)]),
-            added_flour: Mass::new::<gram>(sol[usize::from(added_flour)]),
-            total_water: Mass::new::<gram>(sol[usize::from(total_water)]),
-            added_water: Mass::new::<gram>(sol[usize::from(added_water)]),
-            starter: Mass::new::<gram>(sol[usize::from(starter)]),
-            starter_water: Mass::new::<gram>(sol[usize::from(starter_water)]),
-            salt: Mass::new::<gram>(sol[usize::from(salt)]),
-        };
+impl DoughBalls {
+    /// Weight of the dough once each ingredient is weighed out to the gram, as displayed.
+    pub fn weighed_weight(&self) -> Mass {
+        let weighed = |mass: Mass| mass.get::<gram>().round();
 
-        debug_assert_f64_eq!(bread.total_weight(), Mass::new::<gram>(sol[usize::from(total_weight)]));
-        debug_assert_f64_eq!(bread.hydratation(), hydratation);
-        debug_assert_f64_eq!(
-            bread.starter_flour(),
-            Mass::new::<gram>(sol[usize::from(starter_flour)])
-        );
-        debug_assert_f64_eq!(bread.starter_hydratation(), starter_hydratation);
-        debug_assert_f64_eq!(bread.starter_ratio(), starter_ratio);
+        Mass::new::<gram>(
+            weighed(self.bread.added_flour)
+                + weighed(self.bread.added_water)
+                + weighed(self.bread.starter)
+                + weighed(self.bread.salt),
+        )
+    }
+
+    /// Weight of each ball once the weighed dough is divided evenly.
+    pub fn achieved_ball_weight(&self) -> Mass {
+        self.weighed_weight() / f64::from(self.count)
+    }
+
+    /// Difference between the achieved and the requested ball weight.
+    pub fn deviation(&self) -> Mass {
+        self.achieved_ball_weight() - self.ball_weight
+    }
 
-        bread
-    } else {
-        panic!("should have an optimal point");
+    /// Whether the balls are more than [`BALL_WEIGHT_TOLERANCE`] grams away from the requested weight.
+    pub fn is_off(&self) -> bool {
+        // Masses are stored in kilograms, converting them back to grams leaves noise far below a microgram
+        self.deviation().get::<gram>().abs() - BALL_WEIGHT_TOLERANCE > 1e-6
     }
 }
 
+/// Solves for `count` dough balls of `ball_weight` each, by targeting a total weight of `count × ball_weight`.
+///
+/// The returned balls tell how far the weighed-out dough ends up from that target, see [`DoughBalls::is_off`].
+pub fn solve_dough_balls(
+    count: u32,
+    ball_weight: Mass,
+    hydratation: Ratio,
+    starter_hydratation: Ratio,
+    starter_ratio: Ratio,
+) -> Result<DoughBalls, SolveError> {
+    if count == 0 {
+        return Err(SolveError::InvalidInput("at least one dough ball is needed".to_owned()));
+    }
+
+    if ball_weight.get::<gram>().is_nan() || ball_weight.get::<gram>() <= 0. {
+        return Err(SolveError::InvalidInput(format!(
+            "dough balls must weigh more than 0 g, got {} g",
+            ball_weight.get::<gram>()
+        )));
+    }
+
+    let total_weight = ball_weight * f64::from(count);
+
+    let bread = solve(
+        TargetBread::TotalWeight(total_weight),
+        hydratation,
+        starter_hydratation,
+        starter_ratio,
+    )?;
+
+    let balls = DoughBalls {
+        bread,
+        count,
+        ball_weight,
+    };
+
+    if balls.is_off() {
+        warn!(
+            "Dough balls weigh {} g instead of {} g",
+            balls.achieved_ball_weight().get::<gram>(),
+            ball_weight.get::<gram>()
+        );
+    }
+
+    Ok(balls)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,7 +439,8 @@ mod tests {
             Ratio::new::<ratio>(0.75),
             Ratio::new::<ratio>(0.5),
             Ratio::new::<ratio>(0.2),
-        );
+        )
+        .unwrap();
 
         assert_f64_eq!(bread.total_flour, Mass::new::<gram>(500.));
         assert_f64_eq!(bread.added_flour, Mass::new::<gram>(433.));
@@ -222,7 +458,8 @@ mod tests {
             Ratio::new::<ratio>(0.75),
             Ratio::new::<ratio>(0.5),
             Ratio::new::<ratio>(0.2),
-        );
+        )
+        .unwrap();
 
         assert_f64_eq!(bread.total_flour, Mass::new::<gram>(565.));
         assert_f64_eq!(bread.added_flour, Mass::new::<gram>(490.));
@@ -240,7 +477,8 @@ mod tests {
             Ratio::new::<ratio>(0.75),
             Ratio::new::<ratio>(0.5),
             Ratio::new::<ratio>(0.2),
-        );
+        )
+        .unwrap();
 
         assert_f64_eq!(bread.total_flour, Mass::new::<gram>(400.));
         assert_f64_eq!(bread.added_flour, Mass::new::<gram>(347.));
@@ -250,4 +488,197 @@ mod tests {
         assert_f64_eq!(bread.starter_water, Mass::new::<gram>(26.6666));
         assert_f64_eq!(bread.salt, Mass::new::<gram>(8.));
     }
+
+    #[test]
+    fn solver_by_added_flour() {
+        let bread = solve(
+            TargetBread::AddedFlour(Mass::new::<gram>(400.)),
+            Ratio::new::<ratio>(0.75),
+            Ratio::new::<ratio>(0.5),
+            Ratio::new::<ratio>(0.2),
+        )
+        .unwrap();
+
+        // The starter brings flour on top of the 400 g weighed out, unlike with `TargetBread::Flour`
+        assert_f64_eq!(bread.added_flour, Mass::new::<gram>(400.));
+        assert_f64_eq!(bread.total_flour, Mass::new::<gram>(461.538));
+        assert_f64_eq!(bread.starter, Mass::new::<gram>(92.3077));
+        assert_f64_eq!(bread.total_water, Mass::new::<gram>(346.154));
+    }
+
+    #[test]
+    fn solver_keeps_small_starter() {
+        // The starter is 0.4 g out of 708 g, well below the default tolerance of the total weight
+        let bread = solve(
+            TargetBread::Flour(Mass::new::<gram>(400.)),
+            Ratio::new::<ratio>(0.75),
+            Ratio::new::<ratio>(0.5),
+            Ratio::new::<ratio>(0.001),
+        )
+        .unwrap();
+
+        assert_f64_eq!(bread.starter, Mass::new::<gram>(0.4));
+        assert_f64_eq!(bread.starter_water, Mass::new::<gram>(0.133333));
+        assert_f64_eq!(bread.added_flour + bread.starter_flour(), bread.total_flour);
+        assert_f64_eq!(bread.starter_hydratation(), Ratio::new::<ratio>(0.5));
+    }
+
+    #[test]
+    fn solver_without_starter() {
+        let bread = solve(
+            TargetBread::Flour(Mass::new::<gram>(400.)),
+            Ratio::new::<ratio>(0.75),
+            Ratio::new::<ratio>(0.5),
+            Ratio::new::<ratio>(0.),
+        )
+        .unwrap();
+
+        assert_eq!(bread.starter, Mass::new::<gram>(0.));
+        assert_f64_eq!(bread.added_flour, Mass::new::<gram>(400.));
+    }
+
+    #[test]
+    fn solver_with_tolerance() {
+        let solve_within = |tolerance| {
+            solve_with_tolerance(
+                TargetBread::Flour(Mass::new::<gram>(500.)),
+                Ratio::new::<ratio>(0.7),
+                Ratio::new::<ratio>(1.),
+                Ratio::new::<ratio>(0.2),
+                tolerance,
+            )
+        };
+
+        let bread = solve_within(0.05).unwrap();
+        assert_f64_eq!(bread.total_water(), Mass::new::<gram>(350.));
+
+        for tolerance in [-1., f64::NAN, f64::INFINITY] {
+            assert!(
+                matches!(solve_within(tolerance), Err(SolveError::InvalidInput(_))),
+                "{tolerance}"
+            );
+        }
+    }
+
+    #[test]
+    fn solver_dough_balls() {
+        let balls = solve_dough_balls(
+            4,
+            Mass::new::<gram>(250.),
+            Ratio::new::<ratio>(0.65),
+            Ratio::new::<ratio>(1.),
+            Ratio::new::<ratio>(0.1),
+        )
+        .unwrap();
+
+        assert_f64_eq!(balls.bread.total_weight(), Mass::new::<gram>(1000.));
+        assert!((balls.weighed_weight().get::<gram>() - 1000.).abs() < 1e-9);
+        assert!((balls.achieved_ball_weight().get::<gram>() - 250.).abs() < 1e-9);
+        assert!(!balls.is_off());
+    }
+
+    #[test]
+    fn solver_dough_balls_rounding() {
+        let solve_balls = |count, ball_weight| {
+            solve_dough_balls(
+                count,
+                Mass::new::<gram>(ball_weight),
+                Ratio::new::<ratio>(0.7),
+                Ratio::new::<ratio>(1.),
+                Ratio::new::<ratio>(0.2),
+            )
+            .unwrap()
+        };
+
+        // 209.3 g flour, 139.5 g water, 46.5 g starter and 4.7 g salt are weighed as 401 g
+        let balls = solve_balls(2, 200.);
+        assert!((balls.weighed_weight().get::<gram>() - 401.).abs() < 1e-9);
+        assert!((balls.deviation().get::<gram>() - 0.5).abs() < 1e-9);
+        assert!(!balls.is_off());
+
+        // 105.2 g flour, 70.1 g water, 23.4 g starter and 2.3 g salt are weighed as 200 g
+        let balls = solve_balls(1, 201.);
+        assert!((balls.weighed_weight().get::<gram>() - 200.).abs() < 1e-9);
+        assert!((balls.deviation().get::<gram>() + 1.).abs() < 1e-9);
+        assert!(!balls.is_off());
+
+        // 60.4 g flour, 40.3 g water, 13.4 g starter and 1.3 g salt are weighed as 114 g
+        let balls = solve_balls(1, 115.5);
+        assert!((balls.weighed_weight().get::<gram>() - 114.).abs() < 1e-9);
+        assert!((balls.deviation().get::<gram>() + 1.5).abs() < 1e-9);
+        assert!(balls.is_off());
+    }
+
+    #[test]
+    fn solver_dough_balls_invalid_input() {
+        let solve_balls = |count, ball_weight| {
+            solve_dough_balls(
+                count,
+                Mass::new::<gram>(ball_weight),
+                Ratio::new::<ratio>(0.65),
+                Ratio::new::<ratio>(1.),
+                Ratio::new::<ratio>(0.1),
+            )
+        };
+
+        assert!(matches!(solve_balls(0, 250.), Err(SolveError::InvalidInput(_))));
+        assert!(matches!(solve_balls(4, 0.), Err(SolveError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn solver_by_baked_weight() {
+        let target = TargetBread::baked_weight(Mass::new::<gram>(900.), Ratio::new::<ratio>(0.12)).unwrap();
+        let TargetBread::TotalWeight(raw_weight) = target else {
+            panic!("baked weight should target the total weight");
+        };
+        assert_f64_eq!(raw_weight, Mass::new::<gram>(1022.727));
+
+        let bread = solve(
+            target,
+            Ratio::new::<ratio>(0.75),
+            Ratio::new::<ratio>(0.5),
+            Ratio::new::<ratio>(0.2),
+        )
+        .unwrap();
+
+        assert_f64_eq!(bread.total_weight(), Mass::new::<gram>(1022.727));
+    }
+
+    #[test]
+    fn baked_weight_rejects_invalid_loss() {
+        for loss in [-0.1, 1., 1.5, f64::NAN] {
+            let target = TargetBread::baked_weight(Mass::new::<gram>(900.), Ratio::new::<ratio>(loss));
+            assert!(matches!(target, Err(SolveError::InvalidInput(_))), "{loss}");
+        }
+    }
+
+    #[test]
+    fn solver_rejects_empty_target() {
+        for grams in [0., -100., f64::NAN, f64::INFINITY] {
+            let result = solve(
+                TargetBread::Flour(Mass::new::<gram>(grams)),
+                Ratio::new::<ratio>(0.75),
+                Ratio::new::<ratio>(0.5),
+                Ratio::new::<ratio>(0.2),
+            );
+
+            assert!(
+                matches!(result, Err(SolveError::InvalidInput(_))),
+                "{grams}: {result:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn solver_contradicting_ratios() {
+        // A fixed starter weight can't be reached without any starter
+        let result = solve(
+            TargetBread::Starter(Mass::new::<gram>(100.)),
+            Ratio::new::<ratio>(0.75),
+            Ratio::new::<ratio>(0.5),
+            Ratio::new::<ratio>(0.),
+        );
+
+        assert!(result.is_err(), "{result:?}");
+    }
 }
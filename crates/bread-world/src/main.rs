@@ -48,6 +48,7 @@ fn App() -> Html {
             let target_bread = match target.as_ref() {
                 "total_weight" => TargetBread::TotalWeight(target_value),
                 "flour" => TargetBread::Flour(target_value),
+                "added_flour" => TargetBread::AddedFlour(target_value),
                 "starter" => TargetBread::Starter(target_value),
                 _ => unreachable!(),
             };
@@ -58,10 +59,13 @@ fn App() -> Html {
         }
     };
 
-    let bread_card = bread.as_ref().map(|bread| {
-        html! {
+    let bread_card = bread.as_ref().map(|bread| match bread {
+        Ok(bread) => html! {
             <BreadCard bread={bread.clone()} />
-        }
+        },
+        Err(e) => html! {
+            <p class="error">{ format!("No bread matches these settings: {e}") }</p>
+        },
     });
 
     html! {
@@ -69,6 +73,7 @@ fn App() -> Html {
             <select name="target" ref={target_ref}>
                 <option selected=true value="total_weight">{ "Total Weight (grams)" }</option>
                 <option value="flour">{ "Flour (grams)" }</option>
+                <option value="added_flour">{ "Added Flour (grams)" }</option>
                 <option value="starter">{ "Starter (grams)" }</option>
             </select>
 
@@ -96,26 +101,39 @@ struct BreadCardProps {
 
 #[function_component]
 fn BreadCard(BreadCardProps { bread }: &BreadCardProps) -> Html {
+    let warnings = bread.sanity_warnings();
+    let starter = match bread.starter_style() {
+        Some(style) => format!("{:.0} g ({style})", bread.starter().get::<gram>()),
+        None => format!("{:.0} g", bread.starter().get::<gram>()),
+    };
+
     html! {
-        <table>
-            <tr>
-                <th>{ "Total Weight" }</th>
-                <th>{ "Total Flour" }</th>
-                <th>{ "Added Flour" }</th>
-                <th>{ "Total Water" }</th>
-                <th>{ "Added Water" }</th>
-                <th>{ "Total Starter" }</th>
-                <th>{ "Added Salt" }</th>
-            </tr>
-            <tr>
-                <td>{ format!("{:.0} g", bread.total_weight().get::<gram>()) }</td>
-                <td>{ format!("{:.0} g", bread.total_flour.get::<gram>()) }</td>
-                <td>{ format!("{:.0} g", bread.added_flour.get::<gram>()) }</td>
-                <td>{ format!("{:.0} ml", bread.total_water.get::<gram>()) }</td>
-                <td>{ format!("{:.0} ml", bread.added_water.get::<gram>()) }</td>
-                <td>{ format!("{:.0} g", bread.starter().get::<gram>()) }</td>
-                <td>{ format!("{:.0} g", bread.salt.get::<gram>()) }</td>
-            </tr>
-        </table>
+        <>
+            <table>
+                <tr>
+                    <th>{ "Total Weight" }</th>
+                    <th>{ "Total Flour" }</th>
+                    <th>{ "Added Flour" }</th>
+                    <th>{ "Total Water" }</th>
+                    <th>{ "Added Water" }</th>
+                    <th>{ "Total Starter" }</th>
+                    <th>{ "Added Salt" }</th>
+                </tr>
+                <tr>
+                    <td>{ format!("{:.0} g", bread.total_weight().get::<gram>()) }</td>
+                    <td>{ format!("{:.0} g", bread.total_flour.get::<gram>()) }</td>
+                    <td>{ format!("{:.0} g", bread.added_flour.get::<gram>()) }</td>
+                    <td>{ format!("{:.0} ml", bread.total_water.get::<gram>()) }</td>
+                    <td>{ format!("{:.0} ml", bread.added_water.get::<gram>()) }</td>
+                    <td>{ starter }</td>
+                    <td>{ format!("{:.0} g", bread.salt.get::<gram>()) }</td>
+                </tr>
+            </table>
+            if !warnings.is_empty() {
+                <ul class="warnings">
+                    { for warnings.iter().map(|warning| html! { <li>{ warning.to_string() }</li> }) }
+                </ul>
+            }
+        </>
     }
 }
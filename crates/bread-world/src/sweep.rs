@@ -0,0 +1,137 @@
+//! Solving the same bread over a range of values of one of its ratios.
+
+use bread_world_models::Bread;
+use uom::si::f64::Ratio;
+
+use crate::{SolveError, TargetBread};
+
+/// Maximum number of points solved by a single [`sweep`].
+pub const MAX_SWEEP_POINTS: usize = 100;
+
+/// Ratio varied by a [`sweep`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SweepParameter {
+    Hydratation,
+    StarterHydratation,
+    StarterRatio,
+}
+
+/// Value of the swept parameter, and the bread solved for it.
+pub type SweepPoint = (Ratio, Result<Bread, SolveError>);
+
+/// Range swept by [`sweep`]: `points` evenly spaced values from `from` to `to`, both included.
+#[derive(Clone, Copy, Debug)]
+pub struct Sweep {
+    pub parameter: SweepParameter,
+    pub from: Ratio,
+    pub to: Ratio,
+    pub points: usize,
+}
+
+/// Solves the bread once per value of the swept parameter, the other ratios staying as given.
+///
+/// Each point is solved independently, so one without solution doesn't prevent the others.
+///
+/// Fails if `sweep.points` is zero or greater than [`MAX_SWEEP_POINTS`].
+pub fn sweep(
+    target: TargetBread,
+    hydratation: Ratio,
+    starter_hydratation: Ratio,
+    starter_ratio: Ratio,
+    sweep: Sweep,
+) -> Result<Vec<SweepPoint>, SolveError> {
+    if !(1..=MAX_SWEEP_POINTS).contains(&sweep.points) {
+        return Err(SolveError::InvalidInput(format!(
+            "sweep points must be between 1 and {MAX_SWEEP_POINTS}, got {}",
+            sweep.points
+        )));
+    }
+
+    let step = if sweep.points > 1 {
+        (sweep.to - sweep.from) / (sweep.points - 1) as f64
+    } else {
+        Ratio::default()
+    };
+
+    let points = (0..sweep.points)
+        .map(|i| {
+            let value = sweep.from + step * i as f64;
+
+            let bread = match sweep.parameter {
+                SweepParameter::Hydratation => crate::solve(target, value, starter_hydratation, starter_ratio),
+                SweepParameter::StarterHydratation => crate::solve(target, hydratation, value, starter_ratio),
+                SweepParameter::StarterRatio => crate::solve(target, hydratation, starter_hydratation, value),
+            };
+
+            (value, bread)
+        })
+        .collect();
+
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use uom::si::f64::Mass;
+    use uom::si::mass::gram;
+    use uom::si::ratio::percent;
+
+    use super::*;
+
+    #[test]
+    fn sweep_hydratation() {
+        let points = sweep(
+            TargetBread::Flour(Mass::new::<gram>(500.)),
+            Ratio::new::<percent>(70.),
+            Ratio::new::<percent>(100.),
+            Ratio::new::<percent>(20.),
+            Sweep {
+                parameter: SweepParameter::Hydratation,
+                from: Ratio::new::<percent>(65.),
+                to: Ratio::new::<percent>(80.),
+                points: 4,
+            },
+        )
+        .unwrap();
+
+        let hydratations = points
+            .iter()
+            .map(|(value, _)| value.get::<percent>())
+            .collect::<Vec<_>>();
+        let added_water = points
+            .iter()
+            .map(|(_, bread)| bread.as_ref().unwrap().added_water.get::<gram>())
+            .collect::<Vec<_>>();
+
+        for (hydratation, expected) in hydratations.iter().zip([65., 70., 75., 80.]) {
+            assert!((hydratation - expected).abs() < 1e-9, "{hydratation} != {expected}");
+        }
+
+        // 25 g more water for each 5% of the 500 g of flour
+        for (added_water, expected) in added_water.iter().zip([275., 300., 325., 350.]) {
+            assert!((added_water - expected).abs() < 0.5, "{added_water} != {expected}");
+        }
+    }
+
+    #[test]
+    fn sweep_is_bounded() {
+        let sweep_points = |points| {
+            sweep(
+                TargetBread::Flour(Mass::new::<gram>(500.)),
+                Ratio::new::<percent>(70.),
+                Ratio::new::<percent>(100.),
+                Ratio::new::<percent>(20.),
+                Sweep {
+                    parameter: SweepParameter::Hydratation,
+                    from: Ratio::new::<percent>(0.),
+                    to: Ratio::new::<percent>(100.),
+                    points,
+                },
+            )
+        };
+
+        assert!(sweep_points(0).is_err());
+        assert!(sweep_points(MAX_SWEEP_POINTS + 1).is_err());
+        assert_eq!(sweep_points(1).unwrap().len(), 1);
+    }
+}
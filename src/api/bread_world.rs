@@ -1,6 +1,8 @@
 use axum::{response::IntoResponse, routing::get, Router};
 
-pub fn make_router() -> Router {
+use super::ApiBody;
+
+pub fn make_router() -> Router<(), ApiBody> {
     Router::new().route("/recipes", get(get_recipes))
 }
 
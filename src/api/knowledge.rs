@@ -1,5 +1,7 @@
 use axum::Router;
 
-pub fn make_router() -> Router {
+use super::ApiBody;
+
+pub fn make_router() -> Router<(), ApiBody> {
     Router::new()
 }
@@ -1,10 +1,137 @@
 pub mod bread_world;
 pub mod knowledge;
 
+use axum::body::Body;
+use axum::extract::DefaultBodyLimit;
+use axum::http::header::HeaderName;
+use axum::http::HeaderValue;
 use axum::Router;
+use http_body::Limited;
+use tap::prelude::*;
+use tower_http::limit::RequestBodyLimitLayer;
+use tower_http::set_header::SetResponseHeaderLayer;
 
+const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+
+/// Maximum size in bytes of a request body, larger ones are rejected with `413 Payload Too Large`.
+pub const MAX_BODY_SIZE: usize = 64 * 1024;
+
+/// Request body as seen by the API handlers, which fails once more than [`MAX_BODY_SIZE`] bytes are read.
+pub type ApiBody = Limited<Body>;
+
+/// Serves the API under `/v1`, and under the unversioned root as a deprecated alias.
 pub fn make_router() -> Router {
+    Router::new()
+        .nest("/v1", make_v1_router())
+        .merge(make_v1_router().route_layer(SetResponseHeaderLayer::overriding(
+            DEPRECATION,
+            HeaderValue::from_static("true"),
+        )))
+        .pipe(limit_body)
+}
+
+/// Bounds every request body, whether it is buffered by an extractor or read as a stream.
+///
+/// Requests announcing a larger `Content-Length` are rejected before reaching the handlers.
+fn limit_body(router: Router<(), ApiBody>) -> Router {
+    router
+        .layer(DefaultBodyLimit::max(MAX_BODY_SIZE))
+        .layer(RequestBodyLimitLayer::new(MAX_BODY_SIZE))
+}
+
+fn make_v1_router() -> Router<(), ApiBody> {
     Router::new()
         .nest("/bread-world", bread_world::make_router())
         .nest("/knowledge", knowledge::make_router())
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt as _;
+
+    use super::*;
+
+    async fn get(uri: &str) -> axum::response::Response {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        make_router().oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn v1_is_not_deprecated() {
+        let response = get("/v1/bread-world/recipes").await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(DEPRECATION).is_none());
+    }
+
+    #[tokio::test]
+    async fn unversioned_alias_is_deprecated() {
+        let response = get("/bread-world/recipes").await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[DEPRECATION], "true");
+    }
+
+    #[tokio::test]
+    async fn not_found_is_not_deprecated() {
+        for uri in ["/v1/nope", "/v1/bread-world/nope", "/nope"] {
+            let response = get(uri).await;
+
+            assert_eq!(response.status(), StatusCode::NOT_FOUND, "{uri}");
+            assert!(response.headers().get(DEPRECATION).is_none(), "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn oversized_bodies_are_rejected() {
+        async fn post(size: usize) -> StatusCode {
+            let router = Router::new()
+                .route("/", axum::routing::post(|_: axum::Json<serde_json::Value>| async {}))
+                .pipe(limit_body);
+            let body = serde_json::to_vec(&"a".repeat(size)).unwrap();
+            let request = Request::builder()
+                .method("POST")
+                .uri("/")
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap();
+
+            router.oneshot(request).await.unwrap().status()
+        }
+
+        assert_eq!(post(MAX_BODY_SIZE / 2).await, StatusCode::OK);
+        assert_eq!(post(MAX_BODY_SIZE).await, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn oversized_streams_are_cut() {
+        async fn post(size: usize, content_length: bool) -> StatusCode {
+            let router = Router::new()
+                .route(
+                    "/",
+                    axum::routing::post(|request: Request<ApiBody>| async move {
+                        match hyper::body::to_bytes(request.into_body()).await {
+                            Ok(_) => StatusCode::OK,
+                            Err(_) => StatusCode::IM_A_TEAPOT,
+                        }
+                    }),
+                )
+                .pipe(limit_body);
+            let mut request = Request::builder().method("POST").uri("/");
+            if content_length {
+                request = request.header("content-length", size);
+            }
+            let request = request.body(Body::from(vec![0; size])).unwrap();
+
+            router.oneshot(request).await.unwrap().status()
+        }
+
+        assert_eq!(post(MAX_BODY_SIZE, false).await, StatusCode::OK);
+        // Handlers reading the raw body can't read past the limit
+        assert_eq!(post(MAX_BODY_SIZE + 1, false).await, StatusCode::IM_A_TEAPOT);
+        // And announced oversized bodies don't reach them at all
+        assert_eq!(post(MAX_BODY_SIZE + 1, true).await, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -0,0 +1,154 @@
+use std::io;
+use std::path::PathBuf;
+
+use axum::http::StatusCode;
+use axum::routing::get_service;
+use axum::Router;
+use tokio::fs;
+use tower_http::services::ServeDir;
+
+use crate::config::Config;
+
+pub const DEFAULT_ASSETS_DIR: &str = "./assets/";
+
+/// Where static assets are served from.
+#[derive(Clone, Debug)]
+pub enum Assets {
+    /// Files on disk, under this directory
+    Dir(PathBuf),
+    /// Files compiled into the binary
+    #[cfg(feature = "embed-assets")]
+    Embedded,
+}
+
+impl Assets {
+    /// Uses the configured assets directory if any, the embedded assets otherwise (when available).
+    pub fn from_config(config: &Config) -> Self {
+        match &config.assets_dir {
+            Some(dir) => Self::Dir(dir.clone()),
+            #[cfg(feature = "embed-assets")]
+            None => Self::Embedded,
+            #[cfg(not(feature = "embed-assets"))]
+            None => Self::Dir(PathBuf::from(DEFAULT_ASSETS_DIR)),
+        }
+    }
+
+    pub async fn read_to_string(&self, path: &str) -> io::Result<String> {
+        match self {
+            Self::Dir(dir) => fs::read_to_string(dir.join(path)).await,
+            #[cfg(feature = "embed-assets")]
+            Self::Embedded => {
+                let file = embedded::Embedded::get(path).ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+                String::from_utf8(file.data.into_owned()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+        }
+    }
+
+    /// Serves every asset under its own path.
+    pub fn make_router(&self) -> Router {
+        match self {
+            Self::Dir(dir) => Router::new().route_service(
+                "/*path",
+                get_service(ServeDir::new(dir)).handle_error(|e| async move {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        format!("Unhandled internal error: {e}"),
+                    )
+                }),
+            ),
+            #[cfg(feature = "embed-assets")]
+            Self::Embedded => Router::new().route("/*path", axum::routing::get(embedded::serve)),
+        }
+    }
+}
+
+#[cfg(feature = "embed-assets")]
+mod embedded {
+    use std::time::{Duration, SystemTime};
+
+    use axum::extract::Path;
+    use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+    use axum::response::{IntoResponse, Response};
+    use rust_embed::RustEmbed;
+
+    #[derive(RustEmbed)]
+    #[folder = "assets/"]
+    pub struct Embedded;
+
+    /// Mirrors `ServeDir`: content type guessed from the extension, `Last-Modified` and `If-Modified-Since`.
+    pub async fn serve(Path(path): Path<String>, request_headers: HeaderMap) -> Response {
+        let Some(file) = Embedded::get(&path) else {
+            return StatusCode::NOT_FOUND.into_response();
+        };
+
+        let last_modified = file
+            .metadata
+            .last_modified()
+            .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+
+        let if_modified_since = request_headers
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| httpdate::parse_http_date(value).ok());
+
+        if let (Some(last_modified), Some(if_modified_since)) = (last_modified, if_modified_since) {
+            if last_modified <= if_modified_since {
+                return StatusCode::NOT_MODIFIED.into_response();
+            }
+        }
+
+        let mut headers = HeaderMap::new();
+
+        if let Ok(content_type) = HeaderValue::from_str(file.metadata.mimetype()) {
+            headers.insert(header::CONTENT_TYPE, content_type);
+        }
+
+        if let Some(last_modified) = last_modified {
+            if let Ok(value) = HeaderValue::from_str(&httpdate::fmt_http_date(last_modified)) {
+                headers.insert(header::LAST_MODIFIED, value);
+            }
+        }
+
+        (headers, file.data.into_owned()).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{header, Request};
+    use tower::ServiceExt as _;
+
+    use super::*;
+
+    async fn get(assets: Assets, uri: &str) -> axum::response::Response {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        assets.make_router().oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn serve_from_dir() {
+        let assets = Assets::Dir(PathBuf::from(DEFAULT_ASSETS_DIR));
+
+        let response = get(assets.clone(), "/favicon.ico").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/x-icon");
+
+        let content = assets.read_to_string("bread-world.html").await.unwrap();
+        assert!(content.contains("<html"));
+    }
+
+    #[cfg(feature = "embed-assets")]
+    #[tokio::test]
+    async fn serve_embedded() {
+        let response = get(Assets::Embedded, "/favicon.ico").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/x-icon");
+
+        let response = get(Assets::Embedded, "/does-not-exist.html").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let content = Assets::Embedded.read_to_string("bread-world.html").await.unwrap();
+        assert!(content.contains("<html"));
+    }
+}
@@ -1,47 +1,335 @@
 use std::{
-    net::{IpAddr, Ipv6Addr},
-    path::PathBuf,
+    collections::BTreeMap,
+    fs,
+    net::{AddrParseError, IpAddr, Ipv6Addr, SocketAddr},
+    path::{Path, PathBuf},
+    str::FromStr,
     sync::Arc,
 };
 
+use anyhow::Context as _;
+use serde::Deserialize;
+
+use crate::dev_proxy::Upstream;
+use crate::listen::{Listen, SocketMode};
+use crate::logging::{LogFormat, LogLevel};
+
 pub type ArcConfig = Arc<Config>;
 
+const DEFAULT_CONFIG_FILE: &str = "peculiarzone.toml";
+
+const LAYERS_HELP: &str = "\
+Settings are read from, by increasing priority:
+  1. built-in defaults,
+  2. the TOML configuration file (PECULIARZONE_CONFIG, or ./peculiarzone.toml when present),
+     using the variable names without prefix in lowercase (e.g. `port = 8080`),
+     lists being either comma-separated strings or arrays (e.g. `binding_addr = [\"127.0.0.1\", \"::1\"]`),
+  3. environment variables.";
+
+const ENV_HELP: &str = "\
+Environment variables:
+  PECULIARZONE_CONFIG: Path to the TOML configuration file
+  PECULIARZONE_BINDING_ADDR: Listener binding addresses, comma-separated (e.g. 127.0.0.1,::1)
+  PECULIARZONE_PORT: Listener binding port
+  PECULIARZONE_LISTEN: Listen on 'ip:port' or 'unix:/path/to/socket', instead of the binding address and port set by the same source
+  PECULIARZONE_UNIX_SOCKET_MODE: Permissions of the unix socket file, in octal (e.g. 660)
+  PECULIARZONE_ASSETS_DIR: Directory where assets are to be found (default: ./assets/, or the assets embedded in the binary)
+  PECULIARZONE_READ_ONLY: Reject all API write requests (true or false)
+  PECULIARZONE_DEV_PROXY: Development only, forward requests for the frontend to this server instead of serving the assets (e.g. http://127.0.0.1:8080)
+  PECULIARZONE_LOG_FORMAT: Log output format (pretty or json)
+  PECULIARZONE_LOG_LEVEL: Log level, or filter directives such as info,peculiarzone=debug, overridden by RUST_LOG when set (default: info)";
+
 #[derive(Debug)]
 pub struct Config {
-    pub addr: IpAddr,
+    pub addrs: Vec<IpAddr>,
     pub port: u16,
-    pub assets_dir: PathBuf,
+    /// Where to listen, defaulting to each of `addrs` with `port` over TCP
+    pub listen: Vec<Listen>,
+    /// Permissions of the unix socket file, when listening on one
+    pub unix_socket_mode: Option<SocketMode>,
+    /// Directory where assets are to be found, see [`Assets::from_config`](crate::assets::Assets::from_config)
+    pub assets_dir: Option<PathBuf>,
+    pub read_only: bool,
+    /// Development only: frontend server to forward to instead of serving the assets, see
+    /// [`dev_proxy::make_router`](crate::dev_proxy::make_router)
+    pub dev_proxy: Option<Upstream>,
+    pub log_format: LogFormat,
+    pub log_level: LogLevel,
+    /// Keys of the configuration file that matched no setting
+    pub unknown_keys: Vec<String>,
 }
 
 impl Config {
     pub fn show_help() {
-        println!("HELP:\n{}", env::gen_help());
+        println!("HELP:\n{LAYERS_HELP}\n\n{ENV_HELP}");
+    }
+
+    /// Loads the configuration file, if any, and applies environment overrides on top of it.
+    ///
+    /// This runs before logging is set up, so unknown file keys are reported in `unknown_keys` instead of logged.
+    pub fn load() -> anyhow::Result<Self> {
+        let file = match env::var::<PathBuf>("PECULIARZONE_CONFIG")? {
+            Some(path) => ConfigLayer::from_file(&path)?,
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => ConfigLayer::from_file(Path::new(DEFAULT_CONFIG_FILE))?,
+            None => ConfigLayer::default(),
+        };
+
+        Ok(Self::from_layers(file, ConfigLayer::from_env()?))
     }
 
-    pub fn from_env() -> Self {
-        env::assert_env_vars();
+    fn from_layers(file: ConfigLayer, env: ConfigLayer) -> Self {
+        // A TCP address or port from the environment overrides a listen from the file, as any other setting
+        let env_sets_tcp = env.binding_addr.is_some() || env.port.is_some();
+        let listen = env.listen.or(if env_sets_tcp { None } else { file.listen });
+
+        let addrs = env
+            .binding_addr
+            .or(file.binding_addr)
+            .map_or_else(|| vec![IpAddr::V6(Ipv6Addr::LOCALHOST)], |IpAddrs(addrs)| addrs);
+        let port = env.port.or(file.port).unwrap_or(8888);
+
+        let listen = match listen {
+            Some(listen) => vec![listen],
+            None => addrs
+                .iter()
+                .map(|addr| Listen::Tcp(SocketAddr::new(*addr, port)))
+                .collect(),
+        };
 
         Self {
-            addr: env::addr().unwrap_or(IpAddr::V6(Ipv6Addr::LOCALHOST)),
-            port: env::port().unwrap_or(8888),
-            assets_dir: env::assets_dir().unwrap_or_else(|| PathBuf::from("./assets/")),
+            addrs,
+            port,
+            listen,
+            unix_socket_mode: env.unix_socket_mode.or(file.unix_socket_mode),
+            assets_dir: env.assets_dir.or(file.assets_dir),
+            read_only: env.read_only.or(file.read_only).unwrap_or(false),
+            dev_proxy: env.dev_proxy.or(file.dev_proxy),
+            log_format: env.log_format.or(file.log_format).unwrap_or_default(),
+            log_level: env.log_level.or(file.log_level).unwrap_or_default(),
+            unknown_keys: file.unknown.into_keys().collect(),
+        }
+    }
+}
+
+/// Settings provided by a single configuration source.
+#[derive(Debug, Default, Deserialize)]
+struct ConfigLayer {
+    binding_addr: Option<IpAddrs>,
+    port: Option<u16>,
+    listen: Option<Listen>,
+    unix_socket_mode: Option<SocketMode>,
+    assets_dir: Option<PathBuf>,
+    read_only: Option<bool>,
+    dev_proxy: Option<Upstream>,
+    log_format: Option<LogFormat>,
+    log_level: Option<LogLevel>,
+    #[serde(flatten)]
+    unknown: BTreeMap<String, toml::Value>,
+}
+
+impl ConfigLayer {
+    fn from_env() -> anyhow::Result<Self> {
+        Ok(Self {
+            binding_addr: env::var("PECULIARZONE_BINDING_ADDR")?,
+            port: env::var("PECULIARZONE_PORT")?,
+            listen: env::var("PECULIARZONE_LISTEN")?,
+            unix_socket_mode: env::var("PECULIARZONE_UNIX_SOCKET_MODE")?,
+            assets_dir: env::var("PECULIARZONE_ASSETS_DIR")?,
+            read_only: env::var("PECULIARZONE_READ_ONLY")?,
+            dev_proxy: env::var("PECULIARZONE_DEV_PROXY")?,
+            log_format: env::var("PECULIARZONE_LOG_FORMAT")?,
+            log_level: env::var("PECULIARZONE_LOG_LEVEL")?,
+            unknown: BTreeMap::new(),
+        })
+    }
+
+    fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read configuration file {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Invalid configuration file {}", path.display()))
+    }
+}
+
+/// List of IP addresses, comma-separated in a string, or as an array in the configuration file.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "IpAddrsRepr")]
+pub struct IpAddrs(pub Vec<IpAddr>);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IpAddrsRepr {
+    List(Vec<IpAddr>),
+    Joined(String),
+}
+
+impl FromStr for IpAddrs {
+    type Err = AddrParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|addr| addr.trim().parse())
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl TryFrom<IpAddrsRepr> for IpAddrs {
+    type Error = AddrParseError;
+
+    fn try_from(repr: IpAddrsRepr) -> Result<Self, Self::Error> {
+        match repr {
+            IpAddrsRepr::List(addrs) => Ok(Self(addrs)),
+            IpAddrsRepr::Joined(s) => s.parse(),
         }
     }
 }
 
 mod env {
+    use std::env::VarError;
+    use std::fmt::Display;
+
     use super::*;
 
-    menv::require_envs! {
-        (assert_env_vars, any_set, gen_help);
+    /// Reads and parses the `name` environment variable, if set.
+    pub fn var<T>(name: &str) -> anyhow::Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        match std::env::var(name) {
+            Ok(value) => match value.parse() {
+                Ok(parsed) => Ok(Some(parsed)),
+                Err(e) => anyhow::bail!("Invalid value '{value}' for {name}: {e}"),
+            },
+            Err(VarError::NotPresent) => Ok(None),
+            Err(VarError::NotUnicode(_)) => anyhow::bail!("Invalid value for {name}: not valid unicode"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(content: &str) -> ConfigLayer {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn defaults() {
+        let config = Config::from_layers(ConfigLayer::default(), ConfigLayer::default());
 
-        addr?, "PECULIARZONE_BINDING_ADDR", IpAddr,
-        "PECULIARZONE_BINDING_ADDR: Listener binding address";
+        assert_eq!(config.addrs, [IpAddr::V6(Ipv6Addr::LOCALHOST)]);
+        assert_eq!(config.port, 8888);
+        assert_eq!(config.listen, [Listen::Tcp("[::1]:8888".parse().unwrap())]);
+        assert_eq!(config.assets_dir, None);
+        assert!(!config.read_only);
+        assert_eq!(config.dev_proxy, None);
+        assert_eq!(config.log_format, LogFormat::Pretty);
+        assert_eq!(config.log_level, LogLevel::default());
+    }
+
+    #[test]
+    fn file_overrides_defaults() {
+        let file = file("binding_addr = \"127.0.0.1\"\nport = 8080\nassets_dir = \"/srv/assets\"\n");
+        let config = Config::from_layers(file, ConfigLayer::default());
+
+        assert_eq!(config.addrs, ["127.0.0.1".parse::<IpAddr>().unwrap()]);
+        assert_eq!(config.port, 8080);
+        assert_eq!(config.assets_dir, Some(PathBuf::from("/srv/assets")));
+    }
+
+    #[test]
+    fn env_overrides_file() {
+        let file = file("binding_addr = \"127.0.0.1\"\nport = 8080\nassets_dir = \"/srv/assets\"\nread_only = true\n");
+        let env = ConfigLayer {
+            port: Some(9000),
+            assets_dir: Some(PathBuf::from("/opt/assets")),
+            read_only: Some(false),
+            ..ConfigLayer::default()
+        };
+        let config = Config::from_layers(file, env);
+
+        assert_eq!(config.addrs, ["127.0.0.1".parse::<IpAddr>().unwrap()]);
+        assert_eq!(config.port, 9000);
+        assert_eq!(config.assets_dir, Some(PathBuf::from("/opt/assets")));
+        assert!(!config.read_only);
+    }
+
+    #[test]
+    fn env_tcp_address_overrides_file_listen() {
+        let env = ConfigLayer {
+            port: Some(9000),
+            ..ConfigLayer::default()
+        };
+        let config = Config::from_layers(file("listen = \"unix:/run/peculiarzone.sock\"\n"), env);
+        assert_eq!(config.listen, [Listen::Tcp("[::1]:9000".parse().unwrap())]);
+
+        let env = ConfigLayer {
+            binding_addr: Some("127.0.0.1".parse().unwrap()),
+            ..ConfigLayer::default()
+        };
+        let config = Config::from_layers(file("listen = \"unix:/run/peculiarzone.sock\"\nport = 8080\n"), env);
+        assert_eq!(config.listen, [Listen::Tcp("127.0.0.1:8080".parse().unwrap())]);
+
+        // Within the same layer, listen still wins
+        let env = ConfigLayer {
+            port: Some(9000),
+            listen: Some("unix:/tmp/peculiarzone.sock".parse().unwrap()),
+            ..ConfigLayer::default()
+        };
+        let config = Config::from_layers(ConfigLayer::default(), env);
+        assert_eq!(config.listen, [Listen::Unix(PathBuf::from("/tmp/peculiarzone.sock"))]);
+
+        let config = Config::from_layers(
+            file("listen = \"unix:/run/peculiarzone.sock\"\n"),
+            ConfigLayer::default(),
+        );
+        assert_eq!(config.listen, [Listen::Unix(PathBuf::from("/run/peculiarzone.sock"))]);
+    }
+
+    #[test]
+    fn multiple_binding_addresses() {
+        let expected = [
+            Listen::Tcp("127.0.0.1:8080".parse().unwrap()),
+            Listen::Tcp("[::1]:8080".parse().unwrap()),
+        ];
+
+        let joined = file("binding_addr = \"127.0.0.1, ::1\"\nport = 8080\n");
+        assert_eq!(Config::from_layers(joined, ConfigLayer::default()).listen, expected);
+
+        let array = file("binding_addr = [\"127.0.0.1\", \"::1\"]\nport = 8080\n");
+        let config = Config::from_layers(array, ConfigLayer::default());
+        assert_eq!(config.listen, expected);
+        assert!(config.unknown_keys.is_empty());
+
+        assert!("127.0.0.1,localhost".parse::<IpAddrs>().is_err());
+    }
+
+    #[test]
+    fn invalid_env_value_names_the_variable() {
+        std::env::set_var("PECULIARZONE_TEST_INVALID_PORT", "eighty");
+        let error = env::var::<u16>("PECULIARZONE_TEST_INVALID_PORT").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Invalid value 'eighty' for PECULIARZONE_TEST_INVALID_PORT: invalid digit found in string"
+        );
+        assert_eq!(env::var::<u16>("PECULIARZONE_TEST_UNSET_PORT").unwrap(), None);
+
+        std::env::set_var("PECULIARZONE_TEST_INVALID_LOG_LEVEL", "verbose");
+        let error = env::var::<LogLevel>("PECULIARZONE_TEST_INVALID_LOG_LEVEL").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Invalid value 'verbose' for PECULIARZONE_TEST_INVALID_LOG_LEVEL"));
+    }
 
-        port?, "PECULIARZONE_PORT", u16,
-        "PECULIARZONE_PORT: Listener binding port";
+    #[test]
+    fn unknown_keys_are_collected() {
+        let file = file("port = 8080\nprot = 8081\n[tls]\ncert = \"cert.pem\"\n");
+        let config = Config::from_layers(file, ConfigLayer::default());
 
-        assets_dir?, "PECULIARZONE_ASSETS_DIR", PathBuf,
-        "PECULIARZONE_ASSETS_DIR: Directory where assets are to be found";
+        assert_eq!(config.port, 8080);
+        assert_eq!(config.unknown_keys, ["prot", "tls"]);
     }
 }
@@ -0,0 +1,149 @@
+use std::{fmt, str::FromStr};
+
+use axum::body::Body;
+use axum::http::{header, uri, Request, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use hyper::client::HttpConnector;
+use hyper::Client;
+use serde::Deserialize;
+
+/// Frontend development server the proxy forwards to, as `http://host:port`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Upstream(Uri);
+
+impl FromStr for Upstream {
+    type Err = ParseUpstreamError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let uri = s.parse::<Uri>().map_err(|_| ParseUpstreamError(s.to_owned()))?;
+
+        let is_origin = uri.scheme() == Some(&uri::Scheme::HTTP)
+            && uri.authority().is_some()
+            && matches!(uri.path_and_query().map(uri::PathAndQuery::as_str), None | Some("/"));
+
+        if is_origin {
+            Ok(Self(uri))
+        } else {
+            Err(ParseUpstreamError(s.to_owned()))
+        }
+    }
+}
+
+impl TryFrom<String> for Upstream {
+    type Error = ParseUpstreamError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Upstream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseUpstreamError(String);
+
+impl fmt::Display for ParseUpstreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid development proxy upstream '{}', expected 'http://host:port'",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseUpstreamError {}
+
+/// Forwards every request to the frontend development server, to serve in place of the assets.
+///
+/// The page is then loaded from this server, and reaches the API on the same origin. WebSocket upgrades, such as
+/// the development server's live reload, are not forwarded.
+pub fn make_router(upstream: Upstream) -> Router {
+    tracing::warn!("development proxy to {upstream} enabled, do not use in production");
+
+    let client = Client::new();
+    Router::new().fallback(move |request: Request<Body>| forward(client, upstream, request))
+}
+
+async fn forward(client: Client<HttpConnector>, Upstream(upstream): Upstream, mut request: Request<Body>) -> Response {
+    let mut parts = upstream.into_parts();
+    parts.path_and_query = request.uri().path_and_query().cloned();
+
+    *request.uri_mut() = match Uri::from_parts(parts) {
+        Ok(uri) => uri,
+        Err(e) => return (StatusCode::BAD_REQUEST, format!("Invalid proxied URI: {e}")).into_response(),
+    };
+
+    // Let the client set the upstream host instead
+    request.headers_mut().remove(header::HOST);
+
+    match client.request(request).await {
+        Ok(response) => response.into_response(),
+        Err(e) => (StatusCode::BAD_GATEWAY, format!("Development proxy failure: {e}")).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use axum::routing::get;
+    use tower::ServiceExt as _;
+
+    use super::*;
+
+    /// Serves a fake frontend on a random port, answering with the requested path.
+    fn spawn_frontend() -> SocketAddr {
+        let frontend = Router::new().fallback(|uri: Uri| async move { format!("frontend {uri}") });
+        let server = axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(frontend.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        addr
+    }
+
+    async fn get_body(app: Router, uri: &str) -> (StatusCode, String) {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn proxy_forwards_unrouted_requests() {
+        let upstream = format!("http://{}", spawn_frontend()).parse().unwrap();
+        let app = Router::new()
+            .route("/api/v1/ping", get(|| async { "pong" }))
+            .merge(make_router(upstream));
+
+        assert_eq!(
+            get_body(app.clone(), "/index.html?v=1").await,
+            (StatusCode::OK, "frontend /index.html?v=1".to_owned())
+        );
+        assert_eq!(get_body(app, "/api/v1/ping").await, (StatusCode::OK, "pong".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn unreachable_upstream_is_a_bad_gateway() {
+        // Nothing listens on the discard port
+        let app = make_router("http://127.0.0.1:9".parse().unwrap());
+
+        assert_eq!(get_body(app, "/").await.0, StatusCode::BAD_GATEWAY);
+    }
+
+    #[test]
+    fn parse_upstream() {
+        assert!("http://127.0.0.1:8080".parse::<Upstream>().is_ok());
+        assert!("http://localhost:8080/".parse::<Upstream>().is_ok());
+        assert!("https://localhost:8080".parse::<Upstream>().is_err());
+        assert!("http://localhost:8080/app".parse::<Upstream>().is_err());
+        assert!("localhost:8080".parse::<Upstream>().is_err());
+    }
+}
@@ -1,6 +1,13 @@
 pub mod api;
+pub mod assets;
 pub mod config;
+pub mod dev_proxy;
+pub mod listen;
+pub mod logging;
+pub mod read_only;
+pub mod trace;
 
+use assets::Assets;
 use axum::{
     extract::State,
     http::{Response, StatusCode},
@@ -9,14 +16,14 @@ use axum::{
     Router,
 };
 use config::ArcConfig;
-use tokio::fs;
 
 pub fn make_router(config: ArcConfig) -> Router {
     Router::new().route("/bread-world", get(bread_world)).with_state(config)
 }
 
 pub async fn bread_world(State(config): State<ArcConfig>) -> impl IntoResponse {
-    let content = fs::read_to_string(config.assets_dir.join("bread-world.html"))
+    let content = Assets::from_config(&config)
+        .read_to_string("bread-world.html")
         .await
         .unwrap();
 
@@ -0,0 +1,340 @@
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::Context as _;
+use axum::Router;
+use hyper::server::conn::AddrIncoming;
+use serde::Deserialize;
+use tokio::task::JoinSet;
+
+/// Where the server listens: `ip:port`, or `unix:/path/to/socket`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Listen {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl FromStr for Listen {
+    type Err = ParseListenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = s.strip_prefix("unix:") {
+            if path.is_empty() {
+                Err(ParseListenError(s.to_owned()))
+            } else {
+                Ok(Self::Unix(PathBuf::from(path)))
+            }
+        } else {
+            s.parse().map(Self::Tcp).map_err(|_| ParseListenError(s.to_owned()))
+        }
+    }
+}
+
+impl TryFrom<String> for Listen {
+    type Error = ParseListenError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Listen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "http://{addr}"),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseListenError(String);
+
+impl fmt::Display for ParseListenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid listen address '{}', expected 'ip:port' or 'unix:/path'",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseListenError {}
+
+/// Permissions applied to the unix socket file, written in octal (e.g. `660`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct SocketMode(pub u32);
+
+impl FromStr for SocketMode {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u32::from_str_radix(s, 8).map(Self)
+    }
+}
+
+impl TryFrom<String> for SocketMode {
+    type Error = std::num::ParseIntError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// A bound listener, ready to serve.
+pub enum Listener {
+    Tcp(AddrIncoming),
+    #[cfg(unix)]
+    Unix(unix::UnixAccept),
+}
+
+impl Listener {
+    pub fn bind(listen: &Listen, mode: Option<SocketMode>) -> anyhow::Result<Self> {
+        match listen {
+            Listen::Tcp(addr) => {
+                let incoming = AddrIncoming::bind(addr).with_context(|| format!("Unable to bind {listen}"))?;
+                Ok(Self::Tcp(incoming))
+            }
+            #[cfg(unix)]
+            Listen::Unix(path) => {
+                let accept = unix::UnixAccept::bind(path, mode).with_context(|| format!("Unable to bind {listen}"))?;
+                Ok(Self::Unix(accept))
+            }
+            #[cfg(not(unix))]
+            Listen::Unix(_) => {
+                let _ = mode;
+                anyhow::bail!("Unable to bind {listen}: unix sockets are not supported on this platform")
+            }
+        }
+    }
+
+    pub async fn serve(self, app: Router) -> anyhow::Result<()> {
+        match self {
+            Self::Tcp(incoming) => axum::Server::builder(incoming).serve(app.into_make_service()).await?,
+            #[cfg(unix)]
+            Self::Unix(accept) => axum::Server::builder(accept).serve(app.into_make_service()).await?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Binds every address, failing on the first one that can't be bound.
+pub fn bind_all(listen: &[Listen], mode: Option<SocketMode>) -> anyhow::Result<Vec<Listener>> {
+    listen.iter().map(|listen| Listener::bind(listen, mode)).collect()
+}
+
+/// Serves the same app on every listener concurrently, until one of them fails.
+pub async fn serve_all(listeners: Vec<Listener>, app: Router) -> anyhow::Result<()> {
+    let mut servers = JoinSet::new();
+
+    for listener in listeners {
+        servers.spawn(listener.serve(app.clone()));
+    }
+
+    while let Some(result) = servers.join_next().await {
+        result.context("Server task panicked")??;
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::fs::{self, Permissions};
+    use std::io;
+    use std::os::unix::fs::{FileTypeExt as _, PermissionsExt as _};
+    use std::os::unix::net::UnixStream as StdUnixStream;
+    use std::path::Path;
+    use std::pin::Pin;
+    use std::task::{ready, Context, Poll};
+
+    use hyper::server::accept::Accept;
+    use tokio::net::{UnixListener, UnixStream};
+
+    use super::SocketMode;
+
+    pub struct UnixAccept(UnixListener);
+
+    impl UnixAccept {
+        /// Binds the socket, replacing a stale socket file left by a previous run.
+        ///
+        /// A socket file nobody listens on anymore is stale; one still accepting connections is left alone.
+        pub fn bind(path: &Path, mode: Option<SocketMode>) -> io::Result<Self> {
+            match fs::symlink_metadata(path) {
+                Ok(metadata) if metadata.file_type().is_socket() => match StdUnixStream::connect(path) {
+                    Ok(_) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::AddrInUse,
+                            "another server is listening on this socket",
+                        ))
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => fs::remove_file(path)?,
+                    Err(e) => return Err(e),
+                },
+                Ok(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        "path exists and is not a socket",
+                    ))
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+
+            let listener = UnixListener::bind(path)?;
+
+            if let Some(SocketMode(mode)) = mode {
+                fs::set_permissions(path, Permissions::from_mode(mode))?;
+            }
+
+            Ok(Self(listener))
+        }
+    }
+
+    impl Accept for UnixAccept {
+        type Conn = UnixStream;
+        type Error = io::Error;
+
+        fn poll_accept(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Self::Conn, Self::Error>>> {
+            let (stream, _) = ready!(self.0.poll_accept(cx))?;
+            Poll::Ready(Some(Ok(stream)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_listen() {
+        assert_eq!(
+            "127.0.0.1:8888".parse::<Listen>().unwrap(),
+            Listen::Tcp("127.0.0.1:8888".parse().unwrap())
+        );
+        assert_eq!(
+            "[::1]:8888".parse::<Listen>().unwrap(),
+            Listen::Tcp("[::1]:8888".parse().unwrap())
+        );
+        assert_eq!(
+            "unix:/run/peculiarzone.sock".parse::<Listen>().unwrap(),
+            Listen::Unix(PathBuf::from("/run/peculiarzone.sock"))
+        );
+        assert!("unix:".parse::<Listen>().is_err());
+        assert!("localhost".parse::<Listen>().is_err());
+    }
+
+    #[test]
+    fn parse_socket_mode() {
+        assert_eq!("660".parse::<SocketMode>().unwrap(), SocketMode(0o660));
+        assert!("9".parse::<SocketMode>().is_err());
+    }
+
+    #[tokio::test]
+    async fn serve_on_multiple_addresses() {
+        use axum::routing::get;
+        use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+        use tokio::net::TcpStream;
+
+        let listen = [
+            Listen::Tcp("127.0.0.1:0".parse().unwrap()),
+            Listen::Tcp("[::1]:0".parse().unwrap()),
+        ];
+        let listeners = bind_all(&listen, None).unwrap();
+        let addrs = listeners
+            .iter()
+            .map(|listener| match listener {
+                Listener::Tcp(incoming) => incoming.local_addr(),
+                #[cfg(unix)]
+                Listener::Unix(_) => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+
+        let app = Router::new().route("/", get(|| async { "hello" }));
+        let server = tokio::spawn(serve_all(listeners, app));
+
+        for addr in addrs {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream
+                .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+
+            assert!(response.starts_with("HTTP/1.1 200 OK"), "{addr}: {response}");
+        }
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn bind_all_names_the_failing_address() {
+        let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let taken = Listen::Tcp(taken.local_addr().unwrap());
+
+        let error = bind_all(&[Listen::Tcp("127.0.0.1:0".parse().unwrap()), taken.clone()], None)
+            .err()
+            .unwrap();
+
+        assert_eq!(error.to_string(), format!("Unable to bind {taken}"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn serve_over_unix_socket() {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        use axum::routing::get;
+        use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+        use tokio::net::UnixStream;
+
+        let path = std::env::temp_dir().join(format!("peculiarzone-{}.sock", ulid::Ulid::new()));
+
+        // A stale socket from a previous run must not prevent binding
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+        let listener = Listener::bind(&Listen::Unix(path.clone()), Some(SocketMode(0o660))).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o660);
+
+        let app = Router::new().route("/", get(|| async { "hello" }));
+        let server = tokio::spawn(listener.serve(app));
+
+        let mut stream = UnixStream::connect(&path).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        server.abort();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.ends_with("hello"), "{response}");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn live_unix_socket_is_not_replaced() {
+        let path = std::env::temp_dir().join(format!("peculiarzone-{}.sock", ulid::Ulid::new()));
+        let listen = Listen::Unix(path.clone());
+
+        let _running = Listener::bind(&listen, None).unwrap();
+        let error = Listener::bind(&listen, None).err().unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            format!("{error:#}"),
+            format!("Unable to bind {listen}: another server is listening on this socket")
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use std::{fmt, io, str::FromStr};
+
+use serde::Deserialize;
+use tracing::Subscriber;
+use tracing_subscriber::{filter::LevelFilter, fmt::MakeWriter, util::SubscriberInitExt as _, EnvFilter};
+
+use crate::config::Config;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Pretty,
+    /// One JSON object per line
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = ParseLogFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pretty" => Ok(Self::Pretty),
+            "json" => Ok(Self::Json),
+            _ => Err(ParseLogFormatError(s.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseLogFormatError(String);
+
+impl fmt::Display for ParseLogFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown log format '{}', expected 'pretty' or 'json'", self.0)
+    }
+}
+
+impl std::error::Error for ParseLogFormatError {}
+
+/// Log level (e.g. `debug`) or filter directives (e.g. `info,peculiarzone=debug`), as understood by [`EnvFilter`].
+///
+/// Unlike [`EnvFilter`], a bare word must be a level: targets need an explicit level, so that a misspelled level
+/// is reported instead of read as a target.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct LogLevel(String);
+
+impl LogLevel {
+    fn filter(&self) -> EnvFilter {
+        EnvFilter::new(&self.0)
+    }
+}
+
+impl Default for LogLevel {
+    fn default() -> Self {
+        Self("info".to_owned())
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = ParseLogLevelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bare_word = s
+            .split(',')
+            .map(str::trim)
+            .find(|directive| !directive.contains('=') && directive.parse::<LevelFilter>().is_err());
+
+        if let Some(directive) = bare_word {
+            return Err(ParseLogLevelError(format!(
+                "'{directive}' is not a level, expected one of off, error, warn, info, debug or trace"
+            )));
+        }
+
+        EnvFilter::try_new(s).map_err(|e| ParseLogLevelError(e.to_string()))?;
+
+        Ok(Self(s.to_owned()))
+    }
+}
+
+impl TryFrom<String> for LogLevel {
+    type Error = ParseLogLevelError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseLogLevelError(String);
+
+impl fmt::Display for ParseLogLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid log filter: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseLogLevelError {}
+
+/// Installs the global subscriber. `RUST_LOG`, when set, takes precedence over the configured level.
+pub fn init(config: &Config) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| config.log_level.filter());
+
+    match config.log_format {
+        LogFormat::Pretty => tracing_subscriber::fmt().with_env_filter(filter).finish().init(),
+        LogFormat::Json => json_subscriber(filter, io::stdout).init(),
+    }
+}
+
+fn json_subscriber<W>(filter: EnvFilter, writer: W) -> impl Subscriber + Send + Sync
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt()
+        .json()
+        .flatten_event(true)
+        .with_current_span(true)
+        .with_span_list(false)
+        .with_env_filter(filter)
+        .with_writer(writer)
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::body::Body;
+    use axum::http::Request;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt as _;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn parse_log_format() {
+        assert_eq!("pretty".parse::<LogFormat>().unwrap(), LogFormat::Pretty);
+        assert_eq!("json".parse::<LogFormat>().unwrap(), LogFormat::Json);
+        assert!("yaml".parse::<LogFormat>().is_err());
+    }
+
+    #[test]
+    fn parse_log_level() {
+        assert!("debug".parse::<LogLevel>().is_ok());
+        assert!("info,peculiarzone=trace".parse::<LogLevel>().is_ok());
+        assert!("verbose".parse::<LogLevel>().is_err());
+        assert!("info,peculiarzone=loud".parse::<LogLevel>().is_err());
+    }
+
+    #[tokio::test]
+    async fn json_lines_carry_request_id() {
+        let capture = Capture::default();
+        let subscriber = json_subscriber(EnvFilter::new("info"), {
+            let capture = capture.clone();
+            move || capture.clone()
+        });
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let router = Router::new().route("/", get(|| async { tracing::info!(answer = 42, "handled") }));
+        let request = Request::builder()
+            .uri("/")
+            .header("x-request-id", "smoke-test")
+            .body(Body::empty())
+            .unwrap();
+        crate::trace::layer(router).oneshot(request).await.unwrap();
+
+        let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        let line = output
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .find(|line| line["message"] == "handled")
+            .expect("handler event");
+
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["answer"], 42);
+        assert_eq!(line["span"]["request_id"], "smoke-test");
+        assert!(line["timestamp"].is_string());
+    }
+}
@@ -1,15 +1,11 @@
-use std::net::SocketAddr;
 use std::sync::Arc;
 
 use anyhow::Context as _;
-use axum::http::StatusCode;
-use axum::routing::get_service;
 use axum::Router;
+use peculiarzone::assets::Assets;
 use peculiarzone::config::Config;
+use peculiarzone::listen;
 use tap::prelude::*;
-use tower::ServiceBuilder;
-use tower_http::services::ServeDir;
-use tower_http::trace::TraceLayer;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -20,32 +16,34 @@ async fn main() -> anyhow::Result<()> {
 
     dotenvy::dotenv().context("Failed to load .env file")?;
 
-    let config = Config::from_env().pipe(Arc::new);
+    let config = Config::load().context("Failed to load configuration")?.pipe(Arc::new);
 
-    // enable console logging
-    tracing_subscriber::fmt::init();
+    peculiarzone::logging::init(&config);
+
+    if !config.unknown_keys.is_empty() {
+        tracing::warn!("Unknown keys in configuration file: {}", config.unknown_keys.join(", "));
+    }
+
+    let api = peculiarzone::read_only::layer(peculiarzone::api::make_router(), config.read_only);
+
+    let frontend = match config.dev_proxy.clone() {
+        Some(upstream) => peculiarzone::dev_proxy::make_router(upstream),
+        None => Assets::from_config(&config).make_router(),
+    };
 
     let app = Router::new()
-        .nest("/api", peculiarzone::api::make_router())
+        .nest("/api", api)
         .merge(peculiarzone::make_router(config.clone()))
-        .route_service(
-            "/*path",
-            get_service(ServeDir::new(&config.assets_dir)).handle_error(|e| async move {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Unhandled internal error: {e}"),
-                )
-            }),
-        )
-        .layer(ServiceBuilder::new().layer(TraceLayer::new_for_http()));
-
-    let sock_addr = SocketAddr::new(config.addr, config.port);
-    tracing::info!("listening on http://{}", sock_addr);
-
-    axum::Server::bind(&sock_addr)
-        .serve(app.into_make_service())
-        .await
-        .expect("Unable to start server");
+        .merge(frontend)
+        .pipe(peculiarzone::trace::layer);
+
+    let listeners = listen::bind_all(&config.listen, config.unix_socket_mode)?;
+
+    for listen in &config.listen {
+        tracing::info!("listening on {listen}");
+    }
+
+    listen::serve_all(listeners, app).await.context("Server failure")?;
 
     Ok(())
 }
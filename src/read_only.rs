@@ -0,0 +1,89 @@
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use tower_http::request_id::RequestId;
+
+/// When `read_only` is set, rejects every request that may write (anything but GET, HEAD and OPTIONS).
+pub fn layer(router: Router, read_only: bool) -> Router {
+    if read_only {
+        router.layer(middleware::from_fn(reject_writes))
+    } else {
+        router
+    }
+}
+
+async fn reject_writes(request: Request<Body>, next: Next<Body>) -> Response {
+    if request.method().is_safe() {
+        next.run(request).await
+    } else {
+        // Set by `trace::layer`, which wraps every route
+        let request_id = request
+            .extensions()
+            .get::<RequestId>()
+            .and_then(|id| id.header_value().to_str().ok());
+
+        let body = serde_json::json!({
+            "error": "the server is in read-only mode, write requests are disabled",
+            "request_id": request_id,
+        });
+
+        (StatusCode::SERVICE_UNAVAILABLE, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Method;
+    use axum::routing::get;
+    use tower::ServiceExt as _;
+
+    use super::*;
+
+    async fn send(read_only: bool, method: Method) -> Response {
+        let router = Router::new().route(
+            "/",
+            get(|| async {})
+                .post(|| async {})
+                .patch(|| async {})
+                .delete(|| async {}),
+        );
+        let request = Request::builder()
+            .method(method)
+            .uri("/")
+            .header("x-request-id", "read-only-test")
+            .body(Body::empty())
+            .unwrap();
+
+        crate::trace::layer(layer(router, read_only))
+            .oneshot(request)
+            .await
+            .unwrap()
+    }
+
+    async fn status(read_only: bool, method: Method) -> StatusCode {
+        send(read_only, method).await.status()
+    }
+
+    #[tokio::test]
+    async fn reads_are_allowed() {
+        assert_eq!(status(true, Method::GET).await, StatusCode::OK);
+        assert_eq!(status(false, Method::GET).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn writes_are_rejected_in_read_only_mode() {
+        for method in [Method::POST, Method::PATCH, Method::DELETE] {
+            let response = send(true, method.clone()).await;
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            let body = serde_json::from_slice::<serde_json::Value>(&body).unwrap();
+            assert_eq!(body["request_id"], "read-only-test");
+            assert!(body["error"].as_str().unwrap().contains("read-only"));
+
+            assert_eq!(status(false, method).await, StatusCode::OK);
+        }
+    }
+}
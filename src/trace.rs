@@ -0,0 +1,94 @@
+use axum::body::Body;
+use axum::http::Request;
+use axum::Router;
+use tower::ServiceBuilder;
+use tower_http::request_id::{MakeRequestId, PropagateRequestIdLayer, RequestId, SetRequestIdLayer};
+use tower_http::trace::TraceLayer;
+use tracing::Span;
+use ulid::Ulid;
+
+/// Generates a new Ulid for requests without an `X-Request-Id` header.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MakeRequestUlid;
+
+impl MakeRequestId for MakeRequestUlid {
+    fn make_request_id<B>(&mut self, _: &Request<B>) -> Option<RequestId> {
+        let id = Ulid::new().to_string().parse().ok()?;
+        Some(RequestId::new(id))
+    }
+}
+
+/// Tags every request with an id (honoring an incoming `X-Request-Id`), traces it in a span
+/// carrying that id and returns it in the `X-Request-Id` response header.
+pub fn layer(router: Router) -> Router {
+    router.layer(
+        ServiceBuilder::new()
+            .layer(SetRequestIdLayer::x_request_id(MakeRequestUlid))
+            .layer(TraceLayer::new_for_http().make_span_with(make_span))
+            .layer(PropagateRequestIdLayer::x_request_id()),
+    )
+}
+
+fn make_span(request: &Request<Body>) -> Span {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .and_then(|id| id.header_value().to_str().ok())
+        .unwrap_or_default();
+
+    tracing::info_span!(
+        "request",
+        request_id,
+        method = %request.method(),
+        uri = %request.uri(),
+        version = ?request.version(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::get;
+    use tower::ServiceExt as _;
+
+    use super::*;
+
+    const SEEN_HEADER: &str = "x-seen-request-id";
+
+    fn app() -> Router {
+        let router = Router::new().route(
+            "/",
+            get(|request: Request<Body>| async move {
+                let seen = request.extensions().get::<RequestId>().unwrap().header_value().clone();
+                let mut headers = HeaderMap::new();
+                headers.insert(SEEN_HEADER, seen);
+                (StatusCode::OK, headers)
+            }),
+        );
+
+        layer(router)
+    }
+
+    #[tokio::test]
+    async fn generates_request_id() {
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app().oneshot(request).await.unwrap();
+
+        let id = response.headers()["x-request-id"].to_str().unwrap();
+        assert!(id.parse::<Ulid>().is_ok(), "not a Ulid: {id}");
+        assert_eq!(response.headers()[SEEN_HEADER], id);
+    }
+
+    #[tokio::test]
+    async fn echoes_supplied_request_id() {
+        let request = Request::builder()
+            .uri("/")
+            .header("x-request-id", "my-request")
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+
+        assert_eq!(response.headers()["x-request-id"], "my-request");
+        assert_eq!(response.headers()[SEEN_HEADER], "my-request");
+    }
+}